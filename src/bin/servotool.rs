@@ -0,0 +1,266 @@
+//! # servotool
+//!
+//! A bring-up and calibration CLI for a Feetech servo bus, so probing a bus doesn't require
+//! writing a one-off binary like the teleoperation example.
+//!
+//! ## Commands
+//!
+//! - `ping <port> <id>` — check whether a single ID answers
+//! - `scan <port>` — ping every ID 1..=253 across a list of candidate baud rates
+//! - `read <port> <id> <register>` — read and print one control-table register
+//! - `write <port> <id> <register> <value>` — write one control-table register
+//! - `set-id <port> <id> <new-id>` — change a servo's bus ID
+//! - `set-baud <port> <id> <baud>` — change a servo's baud rate
+//! - `monitor <port> <id> <register> [<register>...]` — continuously poll and print registers
+//!
+//! ## How to run
+//!
+//! ```bash
+//! cargo run --bin servotool -- scan /dev/ttyACM0
+//! ```
+
+use std::env;
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use feetech_servo_rs::{Driver, PingOutcome};
+
+const CANDIDATE_BAUD_RATES: [u32; 9] = [
+    1_000_000, 500_000, 250_000, 128_000, 115_200, 57_600, 38_400, 19_200, 9_600,
+];
+
+/// Feetech's `BAUD_RATE` register doesn't hold the baud rate itself, just an index into this
+/// fixed table.
+const BAUD_RATE_CODES: [(u32, u8); 9] = [
+    (1_000_000, 0),
+    (500_000, 1),
+    (250_000, 2),
+    (128_000, 3),
+    (115_200, 4),
+    (57_600, 5),
+    (38_400, 6),
+    (19_200, 7),
+    (9_600, 8),
+];
+
+type CmdResult = Result<(), String>;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().collect();
+    let Some(verb) = args.get(1) else {
+        print_usage();
+        return ExitCode::FAILURE;
+    };
+
+    let result = match verb.as_str() {
+        "ping" => cmd_ping(&args[2..]),
+        "scan" => cmd_scan(&args[2..]),
+        "read" => cmd_read(&args[2..]),
+        "write" => cmd_write(&args[2..]),
+        "set-id" => cmd_set_id(&args[2..]),
+        "set-baud" => cmd_set_baud(&args[2..]),
+        "monitor" => cmd_monitor(&args[2..]),
+        other => {
+            eprintln!("unknown command: {other}");
+            print_usage();
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_usage() {
+    let registers: Vec<&str> = Driver::register_names().collect();
+    eprintln!(
+        "usage: servotool <command> [args]\n\n\
+         commands:\n  \
+         ping <port> <id>\n  \
+         scan <port>\n  \
+         read <port> <id> <register>\n  \
+         write <port> <id> <register> <value>\n  \
+         set-id <port> <id> <new-id>\n  \
+         set-baud <port> <id> <baud>\n  \
+         monitor <port> <id> <register> [<register>...]\n\n\
+         registers: {}",
+        registers.join(", ")
+    );
+}
+
+fn cmd_ping(args: &[String]) -> CmdResult {
+    let [port, id] = args else {
+        return Err("usage: ping <port> <id>".to_string());
+    };
+    let id = parse_id(id)?;
+    let mut driver = Driver::new(port);
+    match driver.ping(id) {
+        PingOutcome::Present { payload } if !payload.is_empty() => {
+            println!("id {id}: responded, payload {payload:?}")
+        }
+        PingOutcome::Present { .. } => println!("id {id}: responded"),
+        PingOutcome::NotPresent => println!("id {id}: no response"),
+    }
+    Ok(())
+}
+
+/// Ping every ID across every candidate baud rate. Timeout and retries are both cut down from
+/// the defaults since most (baud rate, ID) pairs on a scan are expected to go unanswered.
+fn cmd_scan(args: &[String]) -> CmdResult {
+    let [port] = args else {
+        return Err("usage: scan <port>".to_string());
+    };
+
+    for &baud in &CANDIDATE_BAUD_RATES {
+        println!("-- {baud} baud --");
+        let mut driver = Driver::new_with_baud(port, baud);
+        driver.set_timeout(Duration::from_millis(10));
+        driver.set_retries(0);
+
+        let mut found_any = false;
+        for motor_id in 1u8..=253 {
+            if matches!(driver.ping(motor_id), PingOutcome::Present { .. }) {
+                found_any = true;
+                // Protocol 1.0's Ping reply carries no parameters, so model/firmware come from
+                // a follow-up control-table read rather than the ping payload itself.
+                match (
+                    driver.read_model_number(motor_id),
+                    driver.read_firmware_version(motor_id),
+                ) {
+                    (Ok(model), Ok(firmware)) => {
+                        println!("  id {motor_id}: responded (model {model}, firmware {firmware})")
+                    }
+                    _ => println!("  id {motor_id}: responded"),
+                }
+            }
+        }
+        if !found_any {
+            println!("  (no servos responded)");
+        }
+    }
+    Ok(())
+}
+
+fn cmd_read(args: &[String]) -> CmdResult {
+    let [port, id, register] = args else {
+        return Err("usage: read <port> <id> <register>".to_string());
+    };
+    let id = parse_id(id)?;
+    let mut driver = Driver::new(port);
+    let value = driver
+        .read_register(id, register)
+        .map_err(|err| format!("{register}: {err:?}"))?;
+    println!("{register} = {value}");
+    Ok(())
+}
+
+fn cmd_write(args: &[String]) -> CmdResult {
+    let [port, id, register, value] = args else {
+        return Err("usage: write <port> <id> <register> <value>".to_string());
+    };
+    let id = parse_id(id)?;
+    let value: u16 = value
+        .parse()
+        .map_err(|_| format!("value must be 0-65535, got {value}"))?;
+    let mut driver = Driver::new(port);
+    driver
+        .write_register(id, register, value)
+        .map_err(|err| format!("{register}: {err:?}"))?;
+    println!("{register} <- {value}");
+    Ok(())
+}
+
+fn cmd_set_id(args: &[String]) -> CmdResult {
+    let [port, id, new_id] = args else {
+        return Err("usage: set-id <port> <id> <new-id>".to_string());
+    };
+    let id = parse_id(id)?;
+    let new_id = parse_id(new_id)?;
+    let mut driver = Driver::new(port);
+    driver
+        .write_register(id, "id", u16::from(new_id))
+        .map_err(|err| format!("set-id failed: {err:?}"))?;
+    println!("id {id} -> {new_id}");
+    Ok(())
+}
+
+fn cmd_set_baud(args: &[String]) -> CmdResult {
+    let [port, id, baud] = args else {
+        return Err("usage: set-baud <port> <id> <baud>".to_string());
+    };
+    let id = parse_id(id)?;
+    let baud: u32 = baud
+        .parse()
+        .map_err(|_| format!("baud must be a number, got {baud}"))?;
+    let code = BAUD_RATE_CODES
+        .iter()
+        .find(|(value, _)| *value == baud)
+        .map(|(_, code)| *code)
+        .ok_or_else(|| {
+            let supported: Vec<String> = BAUD_RATE_CODES
+                .iter()
+                .map(|(value, _)| value.to_string())
+                .collect();
+            format!(
+                "unsupported baud rate {baud}, expected one of {}",
+                supported.join(", ")
+            )
+        })?;
+    let mut driver = Driver::new(port);
+    driver
+        .write_register(id, "baud-rate", u16::from(code))
+        .map_err(|err| format!("set-baud failed: {err:?}"))?;
+    println!("id {id}: baud rate -> {baud} (reconnect at the new baud to keep talking to it)");
+    Ok(())
+}
+
+/// Poll `registers` on a fixed period and redraw them in place, reusing the ANSI cursor-rewind
+/// trick the teleoperation example uses for its own live-updating output.
+fn cmd_monitor(args: &[String]) -> CmdResult {
+    let [port, id, registers @ ..] = args else {
+        return Err("usage: monitor <port> <id> <register> [<register>...]".to_string());
+    };
+    if registers.is_empty() {
+        return Err("usage: monitor <port> <id> <register> [<register>...]".to_string());
+    }
+    let id = parse_id(id)?;
+    let mut driver = Driver::new(port);
+
+    let running = Arc::new(AtomicBool::new(true));
+    let running_clone = Arc::clone(&running);
+    ctrlc::set_handler(move || {
+        running_clone.store(false, Ordering::SeqCst);
+    })
+    .map_err(|err| format!("failed to install Ctrl-C handler: {err}"))?;
+
+    while running.load(Ordering::SeqCst) {
+        let mut out = String::new();
+        for name in registers {
+            let line = match driver.read_register(id, name) {
+                Ok(value) => format!("{name:<20} {value}"),
+                Err(err) => format!("{name:<20} <{err:?}>"),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+        print!("{out}");
+        io::stdout().flush().map_err(|err| err.to_string())?;
+        print!("\x1b[{}A", registers.len());
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    println!();
+    Ok(())
+}
+
+fn parse_id(raw: &str) -> Result<u8, String> {
+    raw.parse()
+        .map_err(|_| format!("id must be 0-255, got {raw}"))
+}
@@ -0,0 +1,294 @@
+use std::time::Duration;
+
+use crate::packet_handler::{PacketHandler, RxResult, TxResult};
+use crate::registers::{self, OperatingMode, Register, Width};
+
+pub enum Command {
+    ReadCurrentPosition,
+    WriteTargetPosition(u16),
+    WriteTorqueSwitch(bool),
+}
+
+/// Outcome of pinging a single motor ID, as used by `servotool scan`/`ping`. A status packet's
+/// parameters aren't guaranteed to carry anything on `Ping` (that depends on firmware), so
+/// `payload` is just whatever came back, empty if nothing did.
+pub enum PingOutcome {
+    NotPresent,
+    Present { payload: Vec<u8> },
+}
+
+/// High-level, single-servo entry point on top of `PacketHandler`. `act` issues one bus
+/// transaction per call; see `sync_read_current_positions`/`sync_write_target_positions`
+/// for servicing several servos in one transaction.
+pub struct Driver {
+    handler: PacketHandler,
+}
+
+impl Driver {
+    /// Connect using Protocol 1.0, spoken by the original Feetech servo families.
+    pub fn new(port_name: &str) -> Self {
+        Self {
+            handler: PacketHandler::new(port_name, 1_000_000),
+        }
+    }
+
+    /// Connect using Protocol 2.0, spoken by the newer Feetech/STS control scheme.
+    pub fn new_v2(port_name: &str) -> Self {
+        Self {
+            handler: PacketHandler::new_v2(port_name, 1_000_000),
+        }
+    }
+
+    /// Connect using Protocol 1.0 at an explicit baud rate, for bring-up tools that need to
+    /// probe a bus at something other than the default 1 Mbps (see `servotool scan`).
+    pub fn new_with_baud(port_name: &str, baud_rate: u32) -> Self {
+        Self {
+            handler: PacketHandler::new(port_name, baud_rate),
+        }
+    }
+
+    /// Per-call deadline a single transaction is allowed to block for. Defaults to 50ms.
+    /// `TxError` if the port rejects the new timeout.
+    pub fn set_timeout(&mut self, timeout: Duration) -> TxResult {
+        self.handler.set_timeout(timeout)
+    }
+
+    /// How many times a corrupt or timed-out transaction is resent before giving up. Defaults
+    /// to 2; `servotool scan` sets this to 0 so a baud rate nothing answers on doesn't cost a
+    /// retry per unanswered ID.
+    pub fn set_retries(&mut self, retries: u8) {
+        self.handler.set_retries(retries);
+    }
+
+    /// Check whether `motor_id` is present on the bus.
+    pub fn ping(&mut self, motor_id: u8) -> PingOutcome {
+        match self.handler.ping(motor_id) {
+            RxResult::Success(Some(status)) => PingOutcome::Present {
+                payload: status.params,
+            },
+            _ => PingOutcome::NotPresent,
+        }
+    }
+
+    /// Read any named control-table register (see `registers::names`), widening byte-wide
+    /// registers to `u16` so callers don't have to match on width themselves.
+    pub fn read_register(&mut self, motor_id: u8, name: &str) -> Result<u16, RxResult> {
+        let register = registers::by_name(name).ok_or(RxResult::NotAvailable)?;
+        match register.width {
+            Width::Byte => self.read_u8(motor_id, register).map(u16::from),
+            Width::Word => self.read_u16(motor_id, register),
+        }
+    }
+
+    /// Write any named control-table register (see `registers::names`).
+    pub fn write_register(&mut self, motor_id: u8, name: &str, value: u16) -> Result<(), RxResult> {
+        let register = registers::by_name(name).ok_or(RxResult::NotAvailable)?;
+        self.write_value(motor_id, register, value)
+    }
+
+    /// Every register name `read_register`/`write_register` accept.
+    pub fn register_names() -> impl Iterator<Item = &'static str> {
+        registers::names()
+    }
+
+    pub fn act(&mut self, motor_id: u8, command: Command) -> Result<u16, RxResult> {
+        match command {
+            Command::ReadCurrentPosition => self.read_u16(motor_id, registers::PRESENT_POSITION),
+            Command::WriteTargetPosition(position) => self
+                .write_value(motor_id, registers::GOAL_POSITION, position)
+                .map(|_| position),
+            Command::WriteTorqueSwitch(enabled) => self
+                .write_value(motor_id, registers::TORQUE_ENABLE, enabled as u16)
+                .map(|_| enabled as u16),
+        }
+    }
+
+    /// Read the current position of every motor in `motor_ids` in a single SyncRead
+    /// transaction instead of one `act(ReadCurrentPosition)` round-trip per servo.
+    pub fn sync_read_current_positions(
+        &mut self,
+        motor_ids: &[u8],
+    ) -> Vec<(u8, Result<u16, RxResult>)> {
+        let register = registers::PRESENT_POSITION;
+        let replies = self
+            .handler
+            .sync_read(register.address, register.width.len(), motor_ids);
+        replies
+            .into_iter()
+            .map(|(id, result)| {
+                let position =
+                    result.and_then(|params| register.decode(&params).ok_or(RxResult::RxCorrupt));
+                (id, position)
+            })
+            .collect()
+    }
+
+    /// Write a target position to every `(id, position)` pair in a single SyncWrite
+    /// transaction instead of one `act(WriteTargetPosition)` round-trip per servo.
+    pub fn sync_write_target_positions(&mut self, targets: &[(u8, u16)]) -> TxResult {
+        let servo_data: Vec<(u8, Vec<u8>)> = targets
+            .iter()
+            .map(|(id, position)| (*id, registers::GOAL_POSITION.encode(*position)))
+            .collect();
+        self.handler
+            .sync_write(registers::GOAL_POSITION.address, &servo_data)
+    }
+
+    /// Model number, read back from the control table rather than a `Ping` status payload
+    /// (Protocol 1.0's `Ping` reply carries no parameters at all).
+    pub fn read_model_number(&mut self, motor_id: u8) -> Result<u16, RxResult> {
+        self.read_u16(motor_id, registers::MODEL_NUMBER)
+    }
+
+    /// Firmware version, read back from the control table (see `read_model_number`).
+    pub fn read_firmware_version(&mut self, motor_id: u8) -> Result<u8, RxResult> {
+        self.read_u8(motor_id, registers::FIRMWARE_VERSION)
+    }
+
+    pub fn read_present_speed(&mut self, motor_id: u8) -> Result<u16, RxResult> {
+        self.read_u16(motor_id, registers::PRESENT_SPEED)
+    }
+
+    pub fn read_present_load(&mut self, motor_id: u8) -> Result<u16, RxResult> {
+        self.read_u16(motor_id, registers::PRESENT_LOAD)
+    }
+
+    pub fn read_present_voltage(&mut self, motor_id: u8) -> Result<u8, RxResult> {
+        self.read_u8(motor_id, registers::PRESENT_VOLTAGE)
+    }
+
+    pub fn read_present_temperature(&mut self, motor_id: u8) -> Result<u8, RxResult> {
+        self.read_u8(motor_id, registers::PRESENT_TEMPERATURE)
+    }
+
+    pub fn read_moving(&mut self, motor_id: u8) -> Result<bool, RxResult> {
+        self.read_u8(motor_id, registers::MOVING)
+            .map(|byte| byte != 0)
+    }
+
+    /// Present position, speed and load read back in a single `Read` transaction, since the
+    /// three registers are contiguous in the control table.
+    pub fn read_position_speed_load(&mut self, motor_id: u8) -> Result<(u16, u16, u16), RxResult> {
+        let start = registers::PRESENT_POSITION.address;
+        let total_len = registers::PRESENT_POSITION.width.len()
+            + registers::PRESENT_SPEED.width.len()
+            + registers::PRESENT_LOAD.width.len();
+        match self.handler.read(motor_id, start, total_len) {
+            RxResult::Success(Some(status)) if !status.error.is_ok() => {
+                Err(RxResult::ServoFault(status.error))
+            }
+            RxResult::Success(Some(status)) if status.params.len() >= total_len as usize => {
+                let position = registers::PRESENT_POSITION
+                    .decode(&status.params[0..2])
+                    .ok_or(RxResult::RxCorrupt)?;
+                let speed = registers::PRESENT_SPEED
+                    .decode(&status.params[2..4])
+                    .ok_or(RxResult::RxCorrupt)?;
+                let load = registers::PRESENT_LOAD
+                    .decode(&status.params[4..6])
+                    .ok_or(RxResult::RxCorrupt)?;
+                Ok((position, speed, load))
+            }
+            RxResult::Success(_) => Err(RxResult::RxCorrupt),
+            other => Err(other),
+        }
+    }
+
+    pub fn read_goal_velocity(&mut self, motor_id: u8) -> Result<u16, RxResult> {
+        self.read_u16(motor_id, registers::GOAL_VELOCITY)
+    }
+
+    pub fn write_goal_velocity(&mut self, motor_id: u8, velocity: u16) -> Result<(), RxResult> {
+        self.write_value(motor_id, registers::GOAL_VELOCITY, velocity)
+    }
+
+    pub fn read_min_position_limit(&mut self, motor_id: u8) -> Result<u16, RxResult> {
+        self.read_u16(motor_id, registers::MIN_POSITION_LIMIT)
+    }
+
+    pub fn write_min_position_limit(&mut self, motor_id: u8, limit: u16) -> Result<(), RxResult> {
+        self.write_value(motor_id, registers::MIN_POSITION_LIMIT, limit)
+    }
+
+    pub fn read_max_position_limit(&mut self, motor_id: u8) -> Result<u16, RxResult> {
+        self.read_u16(motor_id, registers::MAX_POSITION_LIMIT)
+    }
+
+    pub fn write_max_position_limit(&mut self, motor_id: u8, limit: u16) -> Result<(), RxResult> {
+        self.write_value(motor_id, registers::MAX_POSITION_LIMIT, limit)
+    }
+
+    pub fn read_operating_mode(&mut self, motor_id: u8) -> Result<OperatingMode, RxResult> {
+        self.read_u8(motor_id, registers::OPERATING_MODE)
+            .map(OperatingMode::from_byte)
+    }
+
+    pub fn write_operating_mode(
+        &mut self,
+        motor_id: u8,
+        mode: OperatingMode,
+    ) -> Result<(), RxResult> {
+        self.write_value(motor_id, registers::OPERATING_MODE, mode.to_byte() as u16)
+    }
+
+    /// P/I/D coefficients of the position (or, in `OperatingMode::Velocity`, the velocity)
+    /// control loop.
+    pub fn read_pid_coefficients(&mut self, motor_id: u8) -> Result<(u8, u8, u8), RxResult> {
+        let p = self.read_u8(motor_id, registers::P_COEFFICIENT)?;
+        let i = self.read_u8(motor_id, registers::I_COEFFICIENT)?;
+        let d = self.read_u8(motor_id, registers::D_COEFFICIENT)?;
+        Ok((p, i, d))
+    }
+
+    pub fn write_pid_coefficients(
+        &mut self,
+        motor_id: u8,
+        p: u8,
+        i: u8,
+        d: u8,
+    ) -> Result<(), RxResult> {
+        self.write_value(motor_id, registers::P_COEFFICIENT, p as u16)?;
+        self.write_value(motor_id, registers::I_COEFFICIENT, i as u16)?;
+        self.write_value(motor_id, registers::D_COEFFICIENT, d as u16)?;
+        Ok(())
+    }
+
+    fn read_u16(&mut self, motor_id: u8, register: Register) -> Result<u16, RxResult> {
+        match self.handler.read_register(motor_id, register) {
+            RxResult::Success(Some(status)) if !status.error.is_ok() => {
+                Err(RxResult::ServoFault(status.error))
+            }
+            RxResult::Success(Some(status)) => {
+                register.decode(&status.params).ok_or(RxResult::RxCorrupt)
+            }
+            RxResult::Success(None) => Err(RxResult::RxFail),
+            other => Err(other),
+        }
+    }
+
+    fn read_u8(&mut self, motor_id: u8, register: Register) -> Result<u8, RxResult> {
+        debug_assert_eq!(register.width, Width::Byte);
+        match self.handler.read_register(motor_id, register) {
+            RxResult::Success(Some(status)) if !status.error.is_ok() => {
+                Err(RxResult::ServoFault(status.error))
+            }
+            RxResult::Success(Some(status)) => {
+                status.params.first().copied().ok_or(RxResult::RxCorrupt)
+            }
+            RxResult::Success(None) => Err(RxResult::RxFail),
+            other => Err(other),
+        }
+    }
+
+    fn write_value(
+        &mut self,
+        motor_id: u8,
+        register: Register,
+        value: u16,
+    ) -> Result<(), RxResult> {
+        match self.handler.write_register(motor_id, register, value) {
+            TxResult::Success => Ok(()),
+            _ => Err(RxResult::RxFail),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+mod driver;
+mod packet_handler;
+mod protocol;
+mod registers;
+mod serial;
+mod trajectory;
+
+pub use driver::{Command, Driver, PingOutcome};
+pub use packet_handler::{RxResult, ServoError, TxResult};
+pub use registers::OperatingMode;
+pub use trajectory::{Frame, Player, Recorder};
@@ -1,20 +1,44 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::protocol::{Protocol, V1, V2};
+use crate::registers::{Access, Register};
 use crate::serial::Serial;
 
-fn compute_checksum(id: u8, length: u8, instruction: u8, parameters: &[u8]) -> u8 {
-    // https://emanual.robotis.com/docs/en/dxl/protocol1/#checksum-instruction-packet
-    let mut checksum: u16 = 0; // avoid overflows, so set as u16
-    checksum += id as u16;
-    checksum += length as u16;
-    checksum += instruction as u16;
-    for param in parameters {
-        checksum += *param as u16;
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(50);
+const DEFAULT_RETRIES: u8 = 2;
+
+/// The error byte every status packet carries, decoded into its individual fault bits.
+/// https://emanual.robotis.com/docs/en/dxl/protocol1/#status-packetreturn-packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ServoError(u8);
+
+impl ServoError {
+    pub const VOLTAGE: ServoError = ServoError(1 << 0);
+    pub const ANGLE_LIMIT: ServoError = ServoError(1 << 1);
+    pub const OVERHEATING: ServoError = ServoError(1 << 2);
+    pub const RANGE: ServoError = ServoError(1 << 3);
+    pub const CHECKSUM: ServoError = ServoError(1 << 4);
+    pub const OVERLOAD: ServoError = ServoError(1 << 5);
+    pub const INSTRUCTION: ServoError = ServoError(1 << 6);
+
+    fn from_byte(byte: u8) -> Self {
+        ServoError(byte)
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, flag: ServoError) -> bool {
+        self.0 & flag.0 != 0
     }
-    (!checksum & 0xff) as u8
 }
 
+#[derive(Clone, Copy)]
 enum Instruction {
     Ping,
     Read,
@@ -25,23 +49,6 @@ enum Instruction {
     SyncRead,
 }
 
-impl Instruction {
-    fn length(&self) -> u8 {
-        // TODO: Do we want to do this like this?
-        // It should be able to calculate it by itself by counting something,
-        // I'm just not sure what it is counting yet
-        match self {
-            Instruction::Ping => 2,
-            Instruction::Read => todo!(),
-            Instruction::Write => todo!(),
-            Instruction::RegWrite => todo!(),
-            Instruction::Action => todo!(),
-            Instruction::SyncWrite => todo!(),
-            Instruction::SyncRead => todo!(),
-        }
-    }
-}
-
 impl From<Instruction> for u8 {
     fn from(value: Instruction) -> Self {
         match value {
@@ -56,81 +63,23 @@ impl From<Instruction> for u8 {
     }
 }
 
-struct InstructionPacket {
-    // https://emanual.robotis.com/docs/en/dxl/protocol1/#instruction-packet
-    // header0: u8,
-    // header1: u8,
-    id: u8,
-    length: u8,
-    instruction: u8,
-    parameters: Vec<u8>,
-    checksum: u8,
-}
-
-impl InstructionPacket {
-    fn new(id: u8, length: u8, instruction: u8) -> Self {
-        let parameters: Vec<u8> = vec![]; // TODO: add parameters
-        Self {
-            // header0: 0xff,
-            // header1: 0xff,
-            id,
-            length,
-            instruction,
-            checksum: compute_checksum(id, length, instruction, &parameters),
-            parameters,
-        }
-    }
-
-    fn get_total_packet_length(&self) -> u32 {
-        // "Header0, Header1, ID, Length" is added to the length of the packet
-        self.length as u32 + 4
-    }
-
-    fn as_bytes(&self) -> Vec<u8> {
-        let mut bytes = vec![
-            0xFF, // The first 2 bytes are always 0xff.
-            0xFF, // AKA. "Header"
-            self.id,
-            self.length,
-            self.instruction,
-        ];
-        bytes.extend_from_slice(&self.parameters);
-        bytes.push(self.checksum);
-        bytes
-    }
-}
-
 pub struct StatusPacket {
     // https://emanual.robotis.com/docs/en/dxl/protocol1/#status-packetreturn-packet
-    id: u8,
-    length: u8,
-    error: u8,
-    params: Vec<u8>,
-    checksum: u8,
+    pub(crate) id: u8,
+    pub(crate) error: ServoError,
+    pub(crate) params: Vec<u8>,
 }
 
-impl StatusPacket {
-    fn new(header: &[u8], id: u8, length: u8, error: u8, params: &[u8], checksum: u8) -> Self {
-        assert!(header == [0xFF, 0xFF]);
-        let computed_checksum = compute_checksum(id, length, error, params);
-        assert!(checksum == computed_checksum); // TODO: handle this
-
-        Self {
-            id,
-            length,
-            error,
-            params: params.to_vec(),
-            checksum,
-        }
+impl std::fmt::Debug for StatusPacket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatusPacket")
+            .field("id", &self.id)
+            .field("error", &self.error)
+            .field("params", &self.params)
+            .finish()
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-enum Endianness {
-    Little,
-    Big,
-}
-
 #[derive(PartialEq, Eq, Debug)]
 pub enum TxResult {
     Success,
@@ -140,6 +89,7 @@ pub enum TxResult {
     NotAvailable,
 }
 
+#[derive(Debug)]
 pub enum RxResult {
     Success(Option<StatusPacket>),
     PortBusy,
@@ -148,77 +98,232 @@ pub enum RxResult {
     RxTimeout,
     RxCorrupt,
     NotAvailable,
+    /// The transaction itself succeeded, but the servo's status packet reported a fault
+    /// (overload, overheating, ...) instead of a clean result.
+    ServoFault(ServoError),
 }
 
 #[derive(Debug)]
 pub struct PacketHandler {
-    endianness: Endianness,
     port: Serial,
+    protocol: Box<dyn Protocol>,
+    retries: u8,
 }
 
 impl PacketHandler {
+    /// Connect using Protocol 1.0 (the original Dynamixel-derived inverted-sum checksum
+    /// scheme most older Feetech servos speak).
     pub fn new(port_name: &str, baud_rate: u32) -> Self {
+        Self::with_protocol(port_name, baud_rate, Box::new(V1))
+    }
+
+    /// Connect using Protocol 2.0 (CRC-16, 0xFD-stuffed) as spoken by the newer
+    /// Feetech/STS control scheme.
+    pub fn new_v2(port_name: &str, baud_rate: u32) -> Self {
+        Self::with_protocol(port_name, baud_rate, Box::new(V2))
+    }
+
+    fn with_protocol(port_name: &str, baud_rate: u32, protocol: Box<dyn Protocol>) -> Self {
         Self {
-            endianness: Endianness::Little,
-            port: Serial::new(port_name, baud_rate).expect("error connecting to serial port"),
+            port: Serial::new(port_name, baud_rate, DEFAULT_TIMEOUT)
+                .expect("error connecting to serial port"),
+            protocol,
+            retries: DEFAULT_RETRIES,
         }
     }
+
+    /// Per-call deadline a single `read_exact` is allowed to block for. Defaults to 50ms.
+    /// `TxError` if the port rejects the new timeout rather than panicking the caller's
+    /// control loop.
+    pub fn set_timeout(&mut self, timeout: Duration) -> TxResult {
+        match self.port.set_timeout(timeout) {
+            Ok(()) => TxResult::Success,
+            Err(_) => TxResult::TxError,
+        }
+    }
+
+    /// How many times a corrupt or timed-out transaction is resent before giving up.
+    /// Defaults to 2.
+    pub fn set_retries(&mut self, retries: u8) {
+        self.retries = retries;
+    }
+
     pub fn ping(&mut self, motor_id: u8) -> RxResult {
-        // TODO: Length is hardcoded here
-        let tx_packet = InstructionPacket::new(motor_id, 2, Instruction::Ping.into());
-        self.tx_rx_packet(tx_packet)
+        let bytes = self
+            .protocol
+            .serialize_instruction(motor_id, Instruction::Ping.into(), &[]);
+        self.tx_rx_packet(motor_id, bytes)
+    }
+
+    pub fn read(&mut self, motor_id: u8, address: u8, length: u8) -> RxResult {
+        let bytes = self.protocol.serialize_instruction(
+            motor_id,
+            Instruction::Read.into(),
+            &[address, length],
+        );
+        self.tx_rx_packet(motor_id, bytes)
+    }
+
+    pub fn write(&mut self, motor_id: u8, address: u8, data: &[u8]) -> TxResult {
+        let mut parameters = vec![address];
+        parameters.extend_from_slice(data);
+        let bytes =
+            self.protocol
+                .serialize_instruction(motor_id, Instruction::Write.into(), &parameters);
+        self.tx_packet(&bytes)
+    }
+
+    /// Read a single control-table register, letting `register`'s width drive how many
+    /// bytes come back instead of the caller hardcoding it.
+    pub(crate) fn read_register(&mut self, motor_id: u8, register: Register) -> RxResult {
+        self.read(motor_id, register.address, register.width.len())
+    }
+
+    /// Write a single control-table register. `value` is encoded per the register's width and
+    /// endianness, so byte-wide registers (torque switch, operating mode, ...) can be written
+    /// with the same call as word-wide ones (goal position, goal velocity, ...). Rejected with
+    /// `TxError` without touching the bus if `register` is `Access::ReadOnly`.
+    pub(crate) fn write_register(
+        &mut self,
+        motor_id: u8,
+        register: Register,
+        value: u16,
+    ) -> TxResult {
+        if register.access == Access::ReadOnly {
+            return TxResult::TxError;
+        }
+        self.write(motor_id, register.address, &register.encode(value))
+    }
+
+    /// Write `data` to every `(id, data)` pair in a single broadcast transaction instead of
+    /// one `write` per servo. All entries must carry the same number of data bytes, which
+    /// becomes the SyncWrite instruction's `data_length` parameter.
+    pub fn sync_write(&mut self, address: u8, servo_data: &[(u8, Vec<u8>)]) -> TxResult {
+        let Some((_, first_data)) = servo_data.first() else {
+            return TxResult::NotAvailable;
+        };
+        let data_length = first_data.len() as u8;
+
+        let mut parameters = vec![address, data_length];
+        for (id, data) in servo_data {
+            if data.len() as u8 != data_length {
+                return TxResult::TxError;
+            }
+            parameters.push(*id);
+            parameters.extend_from_slice(data);
+        }
+
+        let bytes =
+            self.protocol
+                .serialize_instruction(0xFE, Instruction::SyncWrite.into(), &parameters);
+        // Broadcast instructions never get a status packet back, so reuse the existing
+        // 0xFE short-circuit in `tx_rx_packet` instead of duplicating that rule here.
+        match self.tx_rx_packet(0xFE, bytes) {
+            RxResult::Success(_) => TxResult::Success,
+            RxResult::RxFail => TxResult::TxFail,
+            _ => TxResult::NotAvailable,
+        }
     }
 
-    fn tx_rx_packet(&mut self, packet: InstructionPacket) -> RxResult {
-        let result = self.tx_packet(&packet);
-        if result != TxResult::Success {
-            // Eh?
-            return RxResult::RxFail;
+    /// Read `length` bytes starting at `address` from every servo in `motor_ids` in a single
+    /// bus transaction. Each addressed servo answers with its own status packet, in the same
+    /// order `motor_ids` was given, so the reply packets are collected in a loop rather than
+    /// via the usual single `tx_rx_packet` round-trip. A servo that doesn't answer (dropped
+    /// off the bus, miswired, wrong ID) only fails its own entry instead of the whole read.
+    pub fn sync_read(
+        &mut self,
+        address: u8,
+        length: u8,
+        motor_ids: &[u8],
+    ) -> Vec<(u8, Result<Vec<u8>, RxResult>)> {
+        let mut parameters = vec![address, length];
+        parameters.extend_from_slice(motor_ids);
+        let bytes =
+            self.protocol
+                .serialize_instruction(0xFE, Instruction::SyncRead.into(), &parameters);
+
+        if self.tx_packet(&bytes) != TxResult::Success {
+            return motor_ids
+                .iter()
+                .map(|&id| (id, Err(RxResult::RxFail)))
+                .collect();
+        }
+
+        // Servos answer in their own time, not necessarily one-per-slot in request order: a
+        // servo that's missing or slow anywhere but the last position would otherwise shift
+        // every later reply down by one if they were matched positionally. Attribute each
+        // packet to whatever ID it actually carries instead, and only mark an ID as timed
+        // out once every slot has been drained without ever seeing it.
+        let mut pending: Vec<u8> = motor_ids.to_vec();
+        let mut replies: BTreeMap<u8, Result<Vec<u8>, RxResult>> = BTreeMap::new();
+        for _ in 0..motor_ids.len() {
+            if let Ok(status) = self.try_rx_packet() {
+                if pending.contains(&status.id) {
+                    pending.retain(|&id| id != status.id);
+                    replies.insert(status.id, Ok(status.params));
+                }
+            }
+        }
+        for id in pending {
+            replies.insert(id, Err(RxResult::RxTimeout));
         }
-        if packet.id == 0xFE {
-            // WARNING : Status Packet will not be returned if Broadcast ID(0xFE) is used.
-            return RxResult::Success(None);
+
+        motor_ids
+            .iter()
+            .map(|&id| (id, replies.remove(&id).unwrap()))
+            .collect()
+    }
+
+    fn tx_rx_packet(&mut self, id: u8, bytes: Vec<u8>) -> RxResult {
+        for attempt in 0..=self.retries {
+            let result = self.tx_packet(&bytes);
+            if result != TxResult::Success {
+                // Eh?
+                return RxResult::RxFail;
+            }
+            if id == 0xFE {
+                // WARNING : Status Packet will not be returned if Broadcast ID(0xFE) is used.
+                return RxResult::Success(None);
+            }
+            match self.rx_packet() {
+                RxResult::RxTimeout | RxResult::RxCorrupt if attempt < self.retries => {
+                    // A stray byte from this failed attempt could otherwise be mistaken
+                    // for the start of the retry's reply.
+                    let _ = self.port.clear_input();
+                }
+                other => return other,
+            }
         }
-        self.rx_packet()
+        unreachable!("loop always returns on the last attempt")
     }
 
-    fn tx_packet(&mut self, packet: &InstructionPacket) -> TxResult {
-        if packet.get_total_packet_length() > 250 {
+    fn tx_packet(&mut self, bytes: &[u8]) -> TxResult {
+        if bytes.len() > 250 {
             return TxResult::TxError;
         }
-        match self.port.write(&packet.as_bytes()) {
+        match self.port.write(bytes) {
             Ok(_) => TxResult::Success,
             Err(_) => TxResult::TxFail,
         }
     }
 
     fn rx_packet(&mut self) -> RxResult {
-        let mut header: [u8; 2] = [0; 2];
-        self.port
-            .read_exact(&mut header)
-            .expect("reading header failed"); // TODO
-        assert!(header == [0xFF, 0xFF]); // TODO
-        let mut packet: [u8; 3] = [0; 3];
-        self.port
-            .read_exact(&mut packet)
-            .expect("reading packet contents failed"); // TODO
-        let param_len = packet[1];
-        let mut params: Vec<u8> = Vec::with_capacity(param_len.into());
-        self.port
-            .read_exact(&mut params)
-            .expect("reading param contents failed"); // TODO
-        let mut checksum: [u8; 1] = [0; 1];
-        self.port
-            .read_exact(&mut checksum)
-            .expect("reading checksum contents failed"); // TODO
-        let status_packet = StatusPacket::new(
-            &header,
-            packet[0],
-            packet[1],
-            packet[2],
-            &params,
-            checksum[0],
-        );
-        RxResult::Success(Some(status_packet))
+        match self.try_rx_packet() {
+            Ok(status) => RxResult::Success(Some(status)),
+            Err(result) => result,
+        }
+    }
+
+    fn try_rx_packet(&mut self) -> Result<StatusPacket, RxResult> {
+        let frame = self.protocol.read_frame(&mut self.port)?;
+        if !self.protocol.verify(&frame) {
+            return Err(RxResult::RxCorrupt);
+        }
+        let (id, error, params) = self.protocol.parse_status(&frame);
+        Ok(StatusPacket {
+            id,
+            error: ServoError::from_byte(error),
+            params,
+        })
     }
 }
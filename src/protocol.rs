@@ -0,0 +1,273 @@
+use crate::packet_handler::RxResult;
+use crate::serial::Serial;
+
+/// Wire format for talking to a servo. `PacketHandler` is generic over this so the same
+/// high-level code drives both the original Protocol 1.0 (inverted-sum checksum) and the
+/// newer Protocol 2.0 (CRC-16, 0xFD-stuffed) control schemes.
+pub(crate) trait Protocol: std::fmt::Debug {
+    /// Build the full wire bytes (header through checksum/CRC trailer) for one instruction.
+    fn serialize_instruction(&self, id: u8, instruction: u8, parameters: &[u8]) -> Vec<u8>;
+
+    /// Read one full status frame off `port`, header through checksum/CRC trailer.
+    fn read_frame(&self, port: &mut Serial) -> Result<Vec<u8>, RxResult>;
+
+    /// Check the checksum/CRC trailer of a frame previously returned by `read_frame`.
+    fn verify(&self, frame: &[u8]) -> bool;
+
+    /// Pull `(id, error, params)` out of a frame that has already passed `verify`.
+    fn parse_status(&self, frame: &[u8]) -> (u8, u8, Vec<u8>);
+}
+
+fn read_exact(port: &mut Serial, buf: &mut [u8]) -> Result<(), RxResult> {
+    port.read_exact(buf).map_err(|_| RxResult::RxTimeout)
+}
+
+/// Protocol 1.0: https://emanual.robotis.com/docs/en/dxl/protocol1/
+#[derive(Debug, Default)]
+pub(crate) struct V1;
+
+fn checksum_v1(id: u8, length: u8, instruction_or_error: u8, parameters: &[u8]) -> u8 {
+    let mut checksum: u16 = id as u16 + length as u16 + instruction_or_error as u16;
+    for param in parameters {
+        checksum += *param as u16;
+    }
+    (!checksum & 0xff) as u8
+}
+
+impl Protocol for V1 {
+    fn serialize_instruction(&self, id: u8, instruction: u8, parameters: &[u8]) -> Vec<u8> {
+        let length = parameters.len() as u8 + 2;
+        let mut bytes = vec![0xFF, 0xFF, id, length, instruction];
+        bytes.extend_from_slice(parameters);
+        bytes.push(checksum_v1(id, length, instruction, parameters));
+        bytes
+    }
+
+    fn read_frame(&self, port: &mut Serial) -> Result<Vec<u8>, RxResult> {
+        let mut header = [0u8; 2];
+        read_exact(port, &mut header)?;
+        if header != [0xFF, 0xFF] {
+            return Err(RxResult::RxCorrupt);
+        }
+        let mut head = [0u8; 3]; // id, length, error
+        read_exact(port, &mut head)?;
+        // `length` counts the error byte, the parameters and the checksum, so only
+        // `length - 2` bytes of parameters actually follow.
+        let mut rest = vec![0u8; head[1].saturating_sub(2) as usize + 1]; // + checksum byte
+        read_exact(port, &mut rest)?;
+
+        let mut frame = header.to_vec();
+        frame.extend_from_slice(&head);
+        frame.extend_from_slice(&rest);
+        Ok(frame)
+    }
+
+    fn verify(&self, frame: &[u8]) -> bool {
+        let (id, length, error) = (frame[2], frame[3], frame[4]);
+        let params = &frame[5..frame.len() - 1];
+        let checksum = frame[frame.len() - 1];
+        checksum == checksum_v1(id, length, error, params)
+    }
+
+    fn parse_status(&self, frame: &[u8]) -> (u8, u8, Vec<u8>) {
+        let (id, error) = (frame[2], frame[4]);
+        (id, error, frame[5..frame.len() - 1].to_vec())
+    }
+}
+
+/// Protocol 2.0: 3-byte header + reserved byte, little-endian length, CRC-16/IBM trailer
+/// (poly `0x8005`, init `0x0000`, no reflection), with 0xFD-stuffing of the payload so a
+/// `0xFF 0xFF 0xFD` sequence occurring in the data can't be mistaken for a new header.
+#[derive(Debug, Default)]
+pub(crate) struct V2;
+
+const V2_HEADER: [u8; 4] = [0xFF, 0xFF, 0xFD, 0x00];
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0x0000;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn stuff(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        out.push(data[i]);
+        if i + 2 < data.len() && data[i] == 0xFF && data[i + 1] == 0xFF && data[i + 2] == 0xFD {
+            out.push(data[i + 1]);
+            out.push(data[i + 2]);
+            out.push(0xFD);
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+    out
+}
+
+fn destuff(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        if i + 3 < data.len()
+            && data[i] == 0xFF
+            && data[i + 1] == 0xFF
+            && data[i + 2] == 0xFD
+            && data[i + 3] == 0xFD
+        {
+            out.push(0xFF);
+            out.push(0xFF);
+            out.push(0xFD);
+            i += 4;
+        } else {
+            out.push(data[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+impl Protocol for V2 {
+    fn serialize_instruction(&self, id: u8, instruction: u8, parameters: &[u8]) -> Vec<u8> {
+        // Stuff `instruction` together with `parameters` rather than `parameters` alone, so a
+        // 0xFF 0xFF 0xFD sequence straddling the instruction/parameter boundary still gets
+        // caught. `id` and the length field itself stay outside the stuffed region: folding
+        // them in would make `length` depend on its own post-stuffing value (the length bytes
+        // would be part of what they're counting), which nothing sent on a real bus can
+        // actually trigger anyway (it needs `id == 0xFF`, invalid on this bus).
+        let mut unstuffed = vec![instruction];
+        unstuffed.extend_from_slice(parameters);
+        let stuffed = stuff(&unstuffed);
+        let length = stuffed.len() as u16 + 2; // stuffed instruction+params + CRC(2)
+
+        let mut bytes = V2_HEADER.to_vec();
+        bytes.push(id);
+        bytes.extend_from_slice(&length.to_le_bytes());
+        bytes.extend_from_slice(&stuffed);
+
+        let crc = crc16(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    fn read_frame(&self, port: &mut Serial) -> Result<Vec<u8>, RxResult> {
+        let mut header = [0u8; 4];
+        read_exact(port, &mut header)?;
+        if header != V2_HEADER {
+            return Err(RxResult::RxCorrupt);
+        }
+        let mut head = [0u8; 4]; // id, length_lo, length_hi, instruction
+        read_exact(port, &mut head)?;
+        let length = u16::from_le_bytes([head[1], head[2]]) as usize;
+        // `length` counts the instruction byte (already read), the stuffed error+params
+        // payload and the 2-byte CRC trailer.
+        let mut rest = vec![0u8; length.saturating_sub(1 + 2) + 2];
+        read_exact(port, &mut rest)?;
+
+        let mut frame = header.to_vec();
+        frame.extend_from_slice(&head);
+        frame.extend_from_slice(&rest);
+        Ok(frame)
+    }
+
+    fn verify(&self, frame: &[u8]) -> bool {
+        let crc = u16::from_le_bytes([frame[frame.len() - 2], frame[frame.len() - 1]]);
+        crc == crc16(&frame[..frame.len() - 2])
+    }
+
+    fn parse_status(&self, frame: &[u8]) -> (u8, u8, Vec<u8>) {
+        let id = frame[4];
+        let stuffed = &frame[8..frame.len() - 2];
+        let unstuffed = destuff(stuffed);
+        let error = unstuffed.first().copied().unwrap_or(0);
+        let params = unstuffed.get(1..).map(|p| p.to_vec()).unwrap_or_default();
+        (id, error, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_known_vectors() {
+        assert_eq!(crc16(&[]), 0x0000);
+        assert_eq!(crc16(&[0x00]), 0x0000);
+        assert_eq!(crc16(&[0x01]), 0x8005);
+        assert_eq!(crc16(b"abc"), 0xcadb);
+    }
+
+    #[test]
+    fn stuff_inserts_0xfd_after_header_like_sequence() {
+        assert_eq!(
+            stuff(&[0xFF, 0xFF, 0xFD, 0x01]),
+            vec![0xFF, 0xFF, 0xFD, 0xFD, 0x01]
+        );
+    }
+
+    #[test]
+    fn stuff_leaves_unrelated_bytes_alone() {
+        let data = [0x01, 0xFF, 0x02, 0xFD, 0x03];
+        assert_eq!(stuff(&data), data.to_vec());
+    }
+
+    #[test]
+    fn stuff_then_destuff_round_trips() {
+        let cases: &[&[u8]] = &[
+            &[],
+            &[0x01, 0x02, 0x03],
+            &[0xFF, 0xFF, 0xFD, 0x01],
+            &[0x00, 0xFF, 0xFF, 0xFD, 0xFF, 0xFF, 0xFD, 0x7F],
+        ];
+        for data in cases {
+            assert_eq!(destuff(&stuff(data)), data.to_vec());
+        }
+    }
+
+    #[test]
+    fn serialize_instruction_v2_stuffs_across_instruction_param_boundary() {
+        // instruction == 0xFF, followed by params starting 0xFF, 0xFD: the 0xFF 0xFF 0xFD
+        // header-like run straddles the instruction/parameter boundary and must still be
+        // caught by stuffing the two together rather than stuffing `parameters` alone.
+        let bytes = V2.serialize_instruction(1, 0xFF, &[0xFF, 0xFD, 0x01]);
+        // header(4) + id(1) + length(2) = offset 7 is where the stuffed region starts.
+        assert_eq!(&bytes[7..11], &[0xFF, 0xFF, 0xFD, 0xFD]);
+    }
+
+    /// `length`'s meaning differs across protocols (V1 counts error+params+checksum, V2 counts
+    /// instruction+stuffed-payload+CRC) and `read_frame` on each side turns it back into "how
+    /// many more bytes to read" with its own formula; these pin both directions down together
+    /// so the two can't drift apart.
+    #[test]
+    fn v1_length_byte_counts_instruction_params_and_checksum() {
+        for params in [vec![], vec![0xAB], vec![0x01, 0x02, 0x03]] {
+            let bytes = V1.serialize_instruction(1, 2, &params);
+            let length = bytes[3];
+            assert_eq!(length as usize, 1 + params.len() + 1); // instruction + params + checksum
+            assert_eq!(bytes.len(), 4 + length as usize); // header(2) + id + length + `length` more
+        }
+    }
+
+    #[test]
+    fn v2_length_field_counts_stuffed_instruction_params_and_crc() {
+        for params in [vec![], vec![0xAB], vec![0xFF, 0xFF, 0xFD, 0x01]] {
+            let bytes = V2.serialize_instruction(1, 2, &params);
+            let length = u16::from_le_bytes([bytes[5], bytes[6]]) as usize;
+            let mut unstuffed = vec![2u8];
+            unstuffed.extend_from_slice(&params);
+            let stuffed_len = stuff(&unstuffed).len();
+            assert_eq!(length, stuffed_len + 2); // stuffed instruction+params + CRC(2)
+            assert_eq!(bytes.len(), 7 + stuffed_len + 2); // header(4)+id+length(2) + `length` more
+        }
+    }
+}
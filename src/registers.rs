@@ -0,0 +1,169 @@
+//! Feetech STS/SCS control table. Each `Register` carries its address, byte width, access mode
+//! and endianness so `Driver`'s typed accessors never have to pack/unpack bytes by hand —
+//! `Register::encode`/`decode` do that once, generically, driven entirely by this metadata,
+//! and `PacketHandler::write_register` enforces `access` so a `ReadOnly` register can't be
+//! written through the same path.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    ReadOnly,
+    ReadWrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    Byte,
+    Word,
+}
+
+impl Width {
+    pub const fn len(self) -> u8 {
+        match self {
+            Width::Byte => 1,
+            Width::Word => 2,
+        }
+    }
+}
+
+/// Byte order a `Width::Word` register's two bytes are sent/received in. Feetech's own control
+/// table is little-endian throughout, but some registers on other Dynamixel-derived servos
+/// aren't, hence this living on `Register` rather than being a single crate-wide assumption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Endianness {
+    Little,
+    Big,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Register {
+    pub address: u8,
+    pub width: Width,
+    pub access: Access,
+    pub(crate) endianness: Endianness,
+}
+
+impl Register {
+    const fn new(address: u8, width: Width, access: Access) -> Self {
+        Self {
+            address,
+            width,
+            access,
+            endianness: Endianness::Little,
+        }
+    }
+
+    /// Encode `value` to the wire per this register's width and endianness, truncating to a
+    /// single byte for `Width::Byte` registers.
+    pub(crate) fn encode(self, value: u16) -> Vec<u8> {
+        match self.width {
+            Width::Byte => vec![value as u8],
+            Width::Word => match self.endianness {
+                Endianness::Little => value.to_le_bytes().to_vec(),
+                Endianness::Big => value.to_be_bytes().to_vec(),
+            },
+        }
+    }
+
+    /// Decode bytes read back from this register per its width and endianness. `None` if fewer
+    /// bytes than `width` requires came back.
+    pub(crate) fn decode(self, bytes: &[u8]) -> Option<u16> {
+        match self.width {
+            Width::Byte => bytes.first().copied().map(u16::from),
+            Width::Word => {
+                let pair = [*bytes.first()?, *bytes.get(1)?];
+                Some(match self.endianness {
+                    Endianness::Little => u16::from_le_bytes(pair),
+                    Endianness::Big => u16::from_be_bytes(pair),
+                })
+            }
+        }
+    }
+}
+
+pub const FIRMWARE_VERSION: Register = Register::new(0, Width::Byte, Access::ReadOnly);
+pub const MODEL_NUMBER: Register = Register::new(3, Width::Word, Access::ReadOnly);
+
+pub const ID: Register = Register::new(5, Width::Byte, Access::ReadWrite);
+pub const BAUD_RATE: Register = Register::new(6, Width::Byte, Access::ReadWrite);
+
+pub const MIN_POSITION_LIMIT: Register = Register::new(9, Width::Word, Access::ReadWrite);
+pub const MAX_POSITION_LIMIT: Register = Register::new(11, Width::Word, Access::ReadWrite);
+pub const OPERATING_MODE: Register = Register::new(21, Width::Byte, Access::ReadWrite);
+
+// Same three coefficient registers back either the position-mode or the velocity-mode
+// (wheel) control loop, depending on what `OPERATING_MODE` is currently set to.
+pub const P_COEFFICIENT: Register = Register::new(28, Width::Byte, Access::ReadWrite);
+pub const D_COEFFICIENT: Register = Register::new(29, Width::Byte, Access::ReadWrite);
+pub const I_COEFFICIENT: Register = Register::new(30, Width::Byte, Access::ReadWrite);
+
+pub const TORQUE_ENABLE: Register = Register::new(40, Width::Byte, Access::ReadWrite);
+pub const GOAL_POSITION: Register = Register::new(42, Width::Word, Access::ReadWrite);
+pub const GOAL_VELOCITY: Register = Register::new(46, Width::Word, Access::ReadWrite);
+
+pub const PRESENT_POSITION: Register = Register::new(56, Width::Word, Access::ReadOnly);
+pub const PRESENT_SPEED: Register = Register::new(58, Width::Word, Access::ReadOnly);
+pub const PRESENT_LOAD: Register = Register::new(60, Width::Word, Access::ReadOnly);
+pub const PRESENT_VOLTAGE: Register = Register::new(62, Width::Byte, Access::ReadOnly);
+pub const PRESENT_TEMPERATURE: Register = Register::new(63, Width::Byte, Access::ReadOnly);
+pub const MOVING: Register = Register::new(66, Width::Byte, Access::ReadOnly);
+
+/// Every register above, paired with the lowercase-hyphenated name `servotool` accepts on its
+/// `read`/`write`/`monitor` command lines.
+const NAMED_REGISTERS: &[(&str, Register)] = &[
+    ("firmware-version", FIRMWARE_VERSION),
+    ("model-number", MODEL_NUMBER),
+    ("id", ID),
+    ("baud-rate", BAUD_RATE),
+    ("min-position-limit", MIN_POSITION_LIMIT),
+    ("max-position-limit", MAX_POSITION_LIMIT),
+    ("operating-mode", OPERATING_MODE),
+    ("p-coefficient", P_COEFFICIENT),
+    ("d-coefficient", D_COEFFICIENT),
+    ("i-coefficient", I_COEFFICIENT),
+    ("torque-enable", TORQUE_ENABLE),
+    ("goal-position", GOAL_POSITION),
+    ("goal-velocity", GOAL_VELOCITY),
+    ("present-position", PRESENT_POSITION),
+    ("present-speed", PRESENT_SPEED),
+    ("present-load", PRESENT_LOAD),
+    ("present-voltage", PRESENT_VOLTAGE),
+    ("present-temperature", PRESENT_TEMPERATURE),
+    ("moving", MOVING),
+];
+
+/// Look up a control-table register by its `NAMED_REGISTERS` name (case-insensitive).
+pub(crate) fn by_name(name: &str) -> Option<Register> {
+    NAMED_REGISTERS
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, register)| *register)
+}
+
+/// Every name `by_name` accepts, in control-table address order.
+pub(crate) fn names() -> impl Iterator<Item = &'static str> {
+    NAMED_REGISTERS.iter().map(|(name, _)| *name)
+}
+
+/// Value of the `OPERATING_MODE` register: whether the servo is closed-loop on position, or
+/// free-spinning on velocity (wheel mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatingMode {
+    Position,
+    Velocity,
+}
+
+impl OperatingMode {
+    pub(crate) fn to_byte(self) -> u8 {
+        match self {
+            OperatingMode::Position => 0,
+            OperatingMode::Velocity => 1,
+        }
+    }
+
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        match byte {
+            1 => OperatingMode::Velocity,
+            _ => OperatingMode::Position,
+        }
+    }
+}
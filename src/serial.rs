@@ -0,0 +1,54 @@
+use std::io;
+use std::time::Duration;
+
+use serialport::{ClearBuffer, SerialPort};
+
+/// Thin wrapper around the platform serial port so `PacketHandler` doesn't
+/// need to depend on the `serialport` crate's trait object directly.
+pub struct Serial {
+    port: Box<dyn SerialPort>,
+}
+
+impl Serial {
+    pub fn new(port_name: &str, baud_rate: u32, timeout: Duration) -> io::Result<Self> {
+        let port = serialport::new(port_name, baud_rate)
+            .timeout(timeout)
+            .open()?;
+        Ok(Self { port })
+    }
+
+    /// Per-call read deadline. Every `read_exact` blocks at most this long before giving
+    /// up, so a dropped byte fails the call instead of hanging the control loop forever.
+    pub fn set_timeout(&mut self, timeout: Duration) -> io::Result<()> {
+        self.port.set_timeout(timeout)?;
+        Ok(())
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.port.write_all(bytes)?;
+        // Push the bytes out immediately rather than letting them sit in a buffer waiting
+        // for more — the serial equivalent of disabling Nagle's algorithm on a control
+        // socket, where the 40ms of buffering latency would dominate a servo cycle.
+        self.port.flush()
+    }
+
+    pub fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.port.read_exact(buf)
+    }
+
+    /// Drop whatever is sitting in the input buffer. Called before retrying a transaction
+    /// so a late/corrupt reply from the previous attempt can't be mistaken for the new one.
+    pub fn clear_input(&mut self) -> io::Result<()> {
+        self.port.clear(ClearBuffer::Input)?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Serial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Serial")
+            .field("name", &self.port.name())
+            .field("baud_rate", &self.port.baud_rate().ok())
+            .finish()
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::driver::{Command, Driver};
+
+const STEPS_PER_REV: i32 = 4096;
+
+/// One sample of every recorded motor's position, taken at `timestamp` since the start of
+/// the recording.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub timestamp: Duration,
+    pub positions: BTreeMap<u8, u16>,
+}
+
+/// Captures a demonstration by sampling `ReadCurrentPosition` across a fixed set of motors
+/// every time `sample` is called. Call `sample` on your own fixed-period loop (the same
+/// pattern the teleoperation example already drives its leader/follower reads with), then
+/// `save` the result for later playback with `Player`.
+pub struct Recorder {
+    motor_ids: Vec<u8>,
+    frames: Vec<Frame>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub fn new(motor_ids: Vec<u8>) -> Self {
+        Self {
+            motor_ids,
+            frames: Vec::new(),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn sample(&mut self, driver: &mut Driver) {
+        let timestamp = self.start.elapsed();
+        let mut positions = BTreeMap::new();
+        for &motor_id in &self.motor_ids {
+            if let Ok(position) = driver.act(motor_id, Command::ReadCurrentPosition) {
+                positions.insert(motor_id, position);
+            }
+        }
+        self.frames.push(Frame {
+            timestamp,
+            positions,
+        });
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Write the recording as one line per frame: `timestamp_ms id:position id:position ...`.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut out = String::new();
+        for frame in &self.frames {
+            out.push_str(&frame.timestamp.as_millis().to_string());
+            for (motor_id, position) in &frame.positions {
+                out.push_str(&format!(" {motor_id}:{position}"));
+            }
+            out.push('\n');
+        }
+        std::fs::write(path, out)
+    }
+}
+
+/// Plays back a `Recorder` capture without the leader arm attached. The frame buffer and
+/// per-tick targets are precomputed up front by `play`, so the hot loop only has to index
+/// into that buffer and issue a `SyncWrite` — no interpolation work happens mid-playback.
+pub struct Player {
+    frames: Vec<Frame>,
+    speed: f32,
+    looping: bool,
+}
+
+impl Player {
+    pub fn load(path: &str) -> io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let mut frames = Vec::new();
+        for line in content.lines() {
+            let mut fields = line.split_whitespace();
+            let timestamp_ms: u64 = fields
+                .next()
+                .and_then(|field| field.parse().ok())
+                .ok_or_else(|| io::Error::other("malformed trajectory line: missing timestamp"))?;
+
+            let mut positions = BTreeMap::new();
+            for field in fields {
+                let (id, position) = field.split_once(':').ok_or_else(|| {
+                    io::Error::other("malformed trajectory line: expected id:position")
+                })?;
+                let id: u8 = id
+                    .parse()
+                    .map_err(|_| io::Error::other("malformed trajectory line: bad motor id"))?;
+                let position: u16 = position
+                    .parse()
+                    .map_err(|_| io::Error::other("malformed trajectory line: bad position"))?;
+                positions.insert(id, position);
+            }
+
+            frames.push(Frame {
+                timestamp: Duration::from_millis(timestamp_ms),
+                positions,
+            });
+        }
+        Ok(Self {
+            frames,
+            speed: 1.0,
+            looping: false,
+        })
+    }
+
+    /// Playback speed multiplier: `2.0` plays twice as fast, `0.5` half as fast.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Stream the recording back at `tick_period`, one `SyncWrite` per tick, torquing off
+    /// every recorded motor once done.
+    pub fn play(&self, driver: &mut Driver, tick_period: Duration) {
+        let ticks = self.precompute_ticks(tick_period);
+        let motor_ids: Vec<u8> = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.positions.keys().copied())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if ticks.is_empty() {
+            return;
+        }
+
+        loop {
+            for targets in &ticks {
+                driver.sync_write_target_positions(targets);
+                std::thread::sleep(tick_period);
+            }
+            if !self.looping {
+                break;
+            }
+        }
+
+        for motor_id in motor_ids {
+            let _ = driver.act(motor_id, Command::WriteTorqueSwitch(false));
+        }
+    }
+
+    /// Resample the recording onto a `tick_period` grid, linearly interpolating between the
+    /// recorded frames either side of each tick.
+    fn precompute_ticks(&self, tick_period: Duration) -> Vec<Vec<(u8, u16)>> {
+        let Some(duration) = self.frames.last().map(|frame| frame.timestamp) else {
+            return Vec::new();
+        };
+        let motor_ids: BTreeSet<u8> = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.positions.keys().copied())
+            .collect();
+
+        let scaled_tick = tick_period.as_secs_f64() * self.speed as f64;
+        if scaled_tick <= 0.0 {
+            return Vec::new();
+        }
+        let num_ticks = (duration.as_secs_f64() / scaled_tick).ceil() as u64 + 1;
+
+        (0..num_ticks)
+            .map(|tick| {
+                let at = Duration::from_secs_f64(tick as f64 * scaled_tick);
+                motor_ids
+                    .iter()
+                    .filter_map(|&motor_id| {
+                        self.position_at(motor_id, at)
+                            .map(|position| (motor_id, position))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Interpolated position of `motor_id` at `at`, or `None` if no frame ever recorded it
+    /// (frames are allowed to carry different motor-ID sets).
+    fn position_at(&self, motor_id: u8, at: Duration) -> Option<u16> {
+        let samples: Vec<(Duration, u16)> = self
+            .frames
+            .iter()
+            .filter_map(|frame| {
+                frame
+                    .positions
+                    .get(&motor_id)
+                    .map(|&pos| (frame.timestamp, pos))
+            })
+            .collect();
+
+        let (first_t, first_p) = *samples.first()?;
+        if at <= first_t {
+            return Some(first_p);
+        }
+        let (last_t, last_p) = *samples.last()?;
+        if at >= last_t {
+            return Some(last_p);
+        }
+
+        let next = samples.partition_point(|(t, _)| *t <= at);
+        let (t0, p0) = samples[next - 1];
+        let (t1, p1) = samples[next];
+        let span = (t1 - t0).as_secs_f64();
+        let frac = if span > 0.0 {
+            (at - t0).as_secs_f64() / span
+        } else {
+            0.0
+        };
+        Some(lerp_step(p0, p1, frac))
+    }
+}
+
+/// Linearly interpolate between two step positions along the shortest arc of the 0..4096
+/// step space, the same `rem_euclid` wrap-around trick the teleoperation example uses to
+/// convert angles back to steps.
+fn lerp_step(from: u16, to: u16, frac: f64) -> u16 {
+    let mut delta = (to as i32 - from as i32).rem_euclid(STEPS_PER_REV);
+    if delta > STEPS_PER_REV / 2 {
+        delta -= STEPS_PER_REV;
+    }
+    let step = from as i32 + (delta as f64 * frac).round() as i32;
+    step.rem_euclid(STEPS_PER_REV) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lerp_step_interpolates_the_direct_way() {
+        assert_eq!(lerp_step(1000, 2000, 0.0), 1000);
+        assert_eq!(lerp_step(1000, 2000, 0.5), 1500);
+        assert_eq!(lerp_step(1000, 2000, 1.0), 2000);
+    }
+
+    #[test]
+    fn lerp_step_takes_the_shortest_arc_across_the_wrap() {
+        // Going from 4000 to 100 the short way crosses the 4096/0 wrap (delta 196), not the
+        // long way backwards through the 2000s (delta -3900).
+        assert_eq!(lerp_step(4000, 100, 0.5), 2);
+        assert_eq!(lerp_step(4000, 100, 0.0), 4000);
+        assert_eq!(lerp_step(4000, 100, 1.0), 100);
+    }
+
+    fn frame(timestamp_ms: u64, positions: &[(u8, u16)]) -> Frame {
+        Frame {
+            timestamp: Duration::from_millis(timestamp_ms),
+            positions: positions.iter().copied().collect(),
+        }
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!(
+                "feetech-servo-rs-test-{name}-{:?}",
+                std::thread::current().id()
+            ))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn recorder_save_and_player_load_round_trip() {
+        let recorder = Recorder {
+            motor_ids: vec![1, 2],
+            frames: vec![
+                frame(0, &[(1, 10), (2, 20)]),
+                frame(100, &[(1, 30), (2, 40)]),
+            ],
+            start: Instant::now(),
+        };
+        let path = temp_path("round-trip");
+        recorder.save(&path).unwrap();
+        let player = Player::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(player.frames.len(), 2);
+        assert_eq!(player.frames[0].timestamp, Duration::from_millis(0));
+        assert_eq!(player.frames[0].positions, recorder.frames[0].positions);
+        assert_eq!(player.frames[1].timestamp, Duration::from_millis(100));
+        assert_eq!(player.frames[1].positions, recorder.frames[1].positions);
+    }
+
+    #[test]
+    fn player_load_rejects_malformed_lines() {
+        let path = temp_path("malformed");
+        std::fs::write(&path, "not-a-number 1:10\n").unwrap();
+        let result = Player::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn precompute_ticks_interpolates_between_recorded_frames() {
+        let player = Player {
+            frames: vec![frame(0, &[(1, 0)]), frame(100, &[(1, 100)])],
+            speed: 1.0,
+            looping: false,
+        };
+        let ticks = player.precompute_ticks(Duration::from_millis(50));
+        assert_eq!(ticks, vec![vec![(1, 0)], vec![(1, 50)], vec![(1, 100)],]);
+    }
+}